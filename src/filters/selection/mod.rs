@@ -0,0 +1,44 @@
+use serde::Deserialize;
+
+use crate::model::Event;
+
+/// Declarative predicate over events, used by the `selection` filter stage
+///
+/// The same predicate is reused by the n2c source for source-side pushdown, so
+/// a source-side filter and a downstream `selection` stage share one grammar.
+#[derive(Debug, Clone, Deserialize)]
+pub enum Predicate {
+    /// Event whose variant label is in the set (eg: `tx`, `block`, `mint`)
+    VariantIn(Vec<String>),
+    /// Event carrying the given output address
+    OutputAddressEquals(String),
+    /// Event carrying the given policy id
+    PolicyEquals(String),
+    /// Event carrying the given asset id
+    AssetEquals(String),
+    /// Negation of the inner predicate
+    Not(Box<Predicate>),
+    /// True when any inner predicate matches
+    AnyOf(Vec<Predicate>),
+    /// True when every inner predicate matches
+    AllOf(Vec<Predicate>),
+}
+
+impl Predicate {
+    /// Evaluates the predicate against an event
+    pub fn matches(&self, event: &Event) -> bool {
+        match self {
+            Predicate::VariantIn(variants) => variants
+                .iter()
+                .any(|v| v.eq_ignore_ascii_case(event.data.variant_name())),
+            Predicate::OutputAddressEquals(address) => {
+                event.data.output_address() == Some(address.as_str())
+            }
+            Predicate::PolicyEquals(policy) => event.data.policy() == Some(policy.as_str()),
+            Predicate::AssetEquals(asset) => event.data.asset() == Some(asset.as_str()),
+            Predicate::Not(inner) => !inner.matches(event),
+            Predicate::AnyOf(inners) => inners.iter().any(|p| p.matches(event)),
+            Predicate::AllOf(inners) => inners.iter().all(|p| p.matches(event)),
+        }
+    }
+}
@@ -0,0 +1,9 @@
+pub mod filters;
+pub mod mapper;
+pub mod model;
+pub mod pipelining;
+pub mod sources;
+pub mod utils;
+
+/// Boxed error type shared across the pipeline stages
+pub type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
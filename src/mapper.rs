@@ -0,0 +1,150 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+use crate::{
+    filters::selection::Predicate,
+    model::{Event, EventContext, EventData},
+    pipelining::StageSender,
+    utils::Utils,
+    Error,
+};
+
+/// Configuration controlling how ledger entities are mapped into events
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Config {
+    #[serde(default)]
+    pub include_transaction_details: bool,
+
+    #[serde(default)]
+    pub include_block_cbor: bool,
+}
+
+/// Maps ledger entities into [`Event`]s and pushes them onto the output channel
+#[derive(Clone)]
+pub struct EventWriter {
+    output: StageSender,
+    #[allow(dead_code)]
+    utils: Arc<Utils>,
+    #[allow(dead_code)]
+    config: Config,
+    source_filter: Option<Predicate>,
+}
+
+impl EventWriter {
+    pub fn new(output: StageSender, utils: Arc<Utils>, config: Config) -> Self {
+        Self {
+            output,
+            utils,
+            config,
+            source_filter: None,
+        }
+    }
+
+    /// Installs a source-side filter; non-matching events are discarded before
+    /// they are pushed onto the inter-stage channel
+    pub fn with_source_filter(mut self, filter: Option<Predicate>) -> Self {
+        self.source_filter = filter;
+        self
+    }
+
+    /// Wraps a payload with the current context and pushes it down the pipeline
+    ///
+    /// When a source-side filter is installed, events that don't match the
+    /// predicate are dropped here — before they ever reach the inter-stage
+    /// channel — so nothing downstream pays to marshal or move them.
+    pub fn append(&self, data: EventData) -> Result<(), Error> {
+        let event = Event {
+            context: EventContext::default(),
+            data,
+        };
+
+        // control events (rollbacks, reconnect markers) always pass: dropping
+        // them would hide gap/undo signals and let sinks commit rolled-back
+        // blocks
+        if let Some(filter) = &self.source_filter {
+            if !event.data.is_control() && !filter.matches(&event) {
+                return Ok(());
+            }
+        }
+
+        self.output.send(event)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        filters::selection::Predicate,
+        model::EventData,
+        pipelining::new_inter_stage_channel,
+        utils::{ChainWellKnownInfo, Utils},
+    };
+
+    use super::{Config, EventWriter};
+
+    fn writer_with_filter(filter: Predicate) -> (EventWriter, crate::pipelining::StageReceiver) {
+        let (tx, rx) = new_inter_stage_channel(None);
+        let utils = Arc::new(Utils::new(ChainWellKnownInfo::default()).unwrap());
+        let writer = EventWriter::new(tx, utils, Config::default()).with_source_filter(Some(filter));
+        (writer, rx)
+    }
+
+    #[test]
+    fn source_filter_drops_non_matching_events() {
+        let (writer, rx) = writer_with_filter(Predicate::VariantIn(vec!["block".into()]));
+
+        // a non-matching variant must never reach the channel
+        writer.append(EventData::Transaction).unwrap();
+        assert!(rx.try_recv().is_err());
+
+        // a matching variant passes through untouched
+        writer.append(EventData::Block).unwrap();
+        let event = rx.try_recv().expect("matching event should be forwarded");
+        assert_eq!(event.data.variant_name(), "block");
+    }
+
+    #[test]
+    fn source_filter_never_drops_control_events() {
+        let (writer, rx) = writer_with_filter(Predicate::VariantIn(vec!["tx".into()]));
+
+        // rollbacks and reconnect markers must survive even a filter that
+        // matches none of their variants
+        writer
+            .append(EventData::RollBack { block_slot: 42 })
+            .unwrap();
+        assert!(rx.try_recv().is_ok());
+
+        writer
+            .append(EventData::Reconnecting {
+                attempt: 1,
+                last_point: None,
+            })
+            .unwrap();
+        assert!(rx.try_recv().is_ok());
+    }
+
+    #[test]
+    fn source_filter_matches_on_output_address() {
+        let (writer, rx) =
+            writer_with_filter(Predicate::OutputAddressEquals("addr_test".into()));
+
+        writer
+            .append(EventData::TxOutput {
+                address: "addr_other".into(),
+            })
+            .unwrap();
+        assert!(rx.try_recv().is_err());
+
+        writer
+            .append(EventData::TxOutput {
+                address: "addr_test".into(),
+            })
+            .unwrap();
+        assert!(rx.try_recv().is_ok());
+    }
+}
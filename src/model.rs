@@ -0,0 +1,105 @@
+use serde::{Deserialize, Serialize};
+
+/// Metadata attached to every event as it flows through the pipeline
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EventContext {
+    pub block_number: Option<u64>,
+    pub slot: Option<u64>,
+    pub tx_hash: Option<String>,
+    pub output_address: Option<String>,
+}
+
+/// Payload of a pipeline event
+///
+/// Only the variants required by the n2c source and the source-side filter are
+/// modelled here; the full crate carries the rest of the Cardano ledger
+/// entities.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum EventData {
+    Block,
+    Transaction,
+    TxInput,
+    TxOutput {
+        address: String,
+    },
+    OutputAsset {
+        policy: String,
+        asset: String,
+    },
+    Mint {
+        policy: String,
+        asset: String,
+    },
+    Metadata {
+        label: String,
+    },
+    RollBack {
+        block_slot: u64,
+    },
+
+    /// Synthetic event emitted by the chain-sync supervisor before a reconnect
+    ///
+    /// Lets downstream sinks observe the gap left by a dropped connection. The
+    /// `last_point` is the `(slot, block-hash-hex)` the supervisor will resume
+    /// from, when a safely-emitted point is known.
+    Reconnecting {
+        attempt: u32,
+        last_point: Option<(u64, String)>,
+    },
+}
+
+impl EventData {
+    /// Whether this is a control/synthetic event rather than chain data
+    ///
+    /// Control events (rollbacks, reconnect markers) carry gap/undo signals
+    /// that sinks must always see, so they bypass source-side filtering.
+    pub fn is_control(&self) -> bool {
+        matches!(self, EventData::RollBack { .. } | EventData::Reconnecting { .. })
+    }
+
+    /// Lowercase variant label used by the `selection` filter predicates
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            EventData::Block => "block",
+            EventData::Transaction => "tx",
+            EventData::TxInput => "txinput",
+            EventData::TxOutput { .. } => "txoutput",
+            EventData::OutputAsset { .. } => "outputasset",
+            EventData::Mint { .. } => "mint",
+            EventData::Metadata { .. } => "metadata",
+            EventData::RollBack { .. } => "rollback",
+            EventData::Reconnecting { .. } => "reconnecting",
+        }
+    }
+
+    /// Output address carried by the event, when it has one
+    pub fn output_address(&self) -> Option<&str> {
+        match self {
+            EventData::TxOutput { address } => Some(address),
+            _ => None,
+        }
+    }
+
+    /// Policy id carried by the event, when it has one
+    pub fn policy(&self) -> Option<&str> {
+        match self {
+            EventData::OutputAsset { policy, .. } | EventData::Mint { policy, .. } => Some(policy),
+            _ => None,
+        }
+    }
+
+    /// Asset id carried by the event, when it has one
+    pub fn asset(&self) -> Option<&str> {
+        match self {
+            EventData::OutputAsset { asset, .. } | EventData::Mint { asset, .. } => Some(asset),
+            _ => None,
+        }
+    }
+}
+
+/// A single event produced by a source and consumed by sinks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Event {
+    pub context: EventContext,
+    pub data: EventData,
+}
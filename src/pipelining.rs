@@ -0,0 +1,31 @@
+use crossbeam::channel::{bounded, unbounded, Receiver, Sender};
+
+use tokio::task::JoinHandle;
+
+use crate::{model::Event, Error};
+
+/// Sending half of an inter-stage channel
+pub type StageSender = Sender<Event>;
+
+/// Receiving half of an inter-stage channel
+pub type StageReceiver = Receiver<Event>;
+
+/// Creates an inter-stage channel, optionally bounded to `cap` in-flight events
+pub fn new_inter_stage_channel(cap: Option<usize>) -> (StageSender, StageReceiver) {
+    match cap {
+        Some(cap) => bounded(cap),
+        None => unbounded(),
+    }
+}
+
+/// Result of bootstrapping a source: the worker task handle plus its output
+/// channel
+///
+/// The handle is a tokio [`JoinHandle`] so the pipeline can abort the source's
+/// task for a graceful shutdown instead of detaching an OS thread.
+pub type PartialBootstrapResult = Result<(JoinHandle<()>, StageReceiver), Error>;
+
+/// A stage that produces events at the head of a pipeline
+pub trait SourceProvider {
+    fn bootstrap(&self) -> PartialBootstrapResult;
+}
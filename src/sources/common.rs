@@ -0,0 +1,52 @@
+use std::ops::Deref;
+
+use serde::{de::Deserializer, Deserialize};
+
+use pallas::network::miniprotocols::{MAINNET_MAGIC, TESTNET_MAGIC};
+
+/// Transport used to reach the node
+#[derive(Debug, Clone, Deserialize)]
+pub enum BearerKind {
+    Tcp,
+    Unix,
+}
+
+/// Node address: the bearer kind plus the host/path to dial
+#[derive(Debug, Clone, Deserialize)]
+pub struct AddressArg(pub BearerKind, pub String);
+
+/// Network magic, either a well-known alias or an explicit value
+#[derive(Debug, Clone, Deserialize)]
+pub struct MagicArg(pub u64);
+
+impl Deref for MagicArg {
+    type Target = u64;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<&str> for MagicArg {
+    fn from(value: &str) -> Self {
+        match value {
+            "mainnet" => MagicArg(MAINNET_MAGIC),
+            "testnet" => MagicArg(TESTNET_MAGIC),
+            other => MagicArg(other.parse().unwrap_or(MAINNET_MAGIC)),
+        }
+    }
+}
+
+/// A chain point expressed as `(slot, block-hash-hex)`
+#[derive(Debug, Clone, Deserialize)]
+pub struct PointArg(pub u64, pub String);
+
+/// Accepts either a well-known magic alias or a raw numeric magic, both given
+/// as strings; a bare (unquoted) number is rejected
+pub fn deserialize_magic_arg<'de, D>(deserializer: D) -> Result<Option<MagicArg>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let opt = Option::<String>::deserialize(deserializer)?;
+    Ok(opt.map(|s| MagicArg::from(s.as_str())))
+}
@@ -0,0 +1,190 @@
+pub mod common;
+pub mod n2c;
+
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use pallas::network::{
+    miniprotocols::{chainsync, Point},
+    multiplexer::{bearers::Bearer, Channel, StdPlexer},
+};
+
+use crate::{
+    sources::common::{AddressArg, BearerKind, PointArg},
+    utils::Utils,
+    Error,
+};
+
+/// Where the source should start reading the chain from
+#[derive(Debug, Clone, Deserialize)]
+pub enum IntersectArg {
+    Tip,
+    Origin,
+    Point(PointArg),
+    Fallbacks(Vec<PointArg>),
+}
+
+fn default_chainsync_max_retries() -> u32 {
+    50
+}
+
+fn default_chainsync_base_delay_ms() -> u64 {
+    500
+}
+
+fn default_chainsync_backoff_factor() -> f64 {
+    2.0
+}
+
+fn default_chainsync_max_delay_ms() -> u64 {
+    60_000
+}
+
+fn default_chainsync_reset_after_sec() -> u64 {
+    60
+}
+
+/// Retry behaviour for both the initial connection and the chain-sync loop
+///
+/// The `connection_*` fields are accepted but not applied: [`setup_multiplexer`]
+/// dials once, and a failed dial is retried by the chain-sync supervisor like
+/// any other connection error. The `chainsync_*` fields drive that supervisor,
+/// which reconnects a dropped chain-sync loop with exponential backoff (see
+/// [`n2c::setup`]).
+#[derive(Debug, Clone, Deserialize)]
+pub struct RetryPolicy {
+    #[serde(default)]
+    pub connection_max_retries: u32,
+
+    #[serde(default)]
+    pub connection_max_backoff: u32,
+
+    /// Max reconnect attempts before the supervisor gives up
+    #[serde(default = "default_chainsync_max_retries")]
+    pub chainsync_max_retries: u32,
+
+    /// Base delay (ms) for the first reconnect
+    #[serde(default = "default_chainsync_base_delay_ms")]
+    pub chainsync_base_delay_ms: u64,
+
+    /// Exponential factor applied per attempt
+    #[serde(default = "default_chainsync_backoff_factor")]
+    pub chainsync_backoff_factor: f64,
+
+    /// Ceiling (ms) on the exponential backoff delay, before any jitter
+    #[serde(default = "default_chainsync_max_delay_ms")]
+    pub chainsync_max_delay_ms: u64,
+
+    /// Spread reconnects with random jitter to avoid thundering herds
+    #[serde(default)]
+    pub chainsync_jitter: bool,
+
+    /// A connection that stays up at least this long (s) resets the counter
+    #[serde(default = "default_chainsync_reset_after_sec")]
+    pub chainsync_reset_after_sec: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            connection_max_retries: 0,
+            connection_max_backoff: 0,
+            chainsync_max_retries: default_chainsync_max_retries(),
+            chainsync_base_delay_ms: default_chainsync_base_delay_ms(),
+            chainsync_backoff_factor: default_chainsync_backoff_factor(),
+            chainsync_max_delay_ms: default_chainsync_max_delay_ms(),
+            chainsync_jitter: false,
+            chainsync_reset_after_sec: default_chainsync_reset_after_sec(),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn chainsync_base_delay(&self) -> Duration {
+        Duration::from_millis(self.chainsync_base_delay_ms)
+    }
+
+    pub fn chainsync_max_delay(&self) -> Duration {
+        Duration::from_millis(self.chainsync_max_delay_ms)
+    }
+
+    pub fn chainsync_reset_after(&self) -> Duration {
+        Duration::from_secs(self.chainsync_reset_after_sec)
+    }
+}
+
+/// Dials the node and sets up a multiplexer over the requested mini-protocols
+pub async fn setup_multiplexer(
+    bearer: &BearerKind,
+    address: &str,
+    protocols: &[u16],
+    _retry: &Option<RetryPolicy>,
+) -> Result<StdPlexer, Error> {
+    let bearer = match bearer {
+        BearerKind::Tcp => Bearer::connect_tcp(address).await?,
+        BearerKind::Unix => Bearer::connect_unix(address).await?,
+    };
+
+    let mut plexer = StdPlexer::new(bearer);
+
+    for protocol in protocols {
+        plexer.use_channel(*protocol);
+    }
+
+    Ok(plexer)
+}
+
+/// Resolves the configured intersect into a set of candidate chain points
+pub async fn define_start_point(
+    intersect: &Option<IntersectArg>,
+    since: &Option<PointArg>,
+    _utils: &Utils,
+    channel: &mut Channel,
+) -> Result<Option<Vec<Point>>, Error> {
+    let mut client = chainsync::N2CClient::new(channel);
+
+    let points = match intersect {
+        Some(IntersectArg::Origin) => {
+            client.intersect_origin().await?;
+            None
+        }
+        Some(IntersectArg::Tip) => {
+            let point = client.intersect_tip().await?;
+            Some(vec![point])
+        }
+        Some(IntersectArg::Point(p)) => Some(vec![point_from_arg(p)?]),
+        Some(IntersectArg::Fallbacks(ps)) => {
+            Some(ps.iter().map(point_from_arg).collect::<Result<_, _>>()?)
+        }
+        #[allow(deprecated)]
+        None => match since {
+            Some(p) => Some(vec![point_from_arg(p)?]),
+            None => {
+                client.intersect_origin().await?;
+                None
+            }
+        },
+    };
+
+    if let Some(points) = &points {
+        client.find_intersect(points.clone()).await?;
+    }
+
+    Ok(points)
+}
+
+fn point_from_arg(arg: &PointArg) -> Result<Point, Error> {
+    let hash = hex::decode(&arg.1)?;
+    Ok(Point::Specific(arg.0, hash))
+}
+
+impl AddressArg {
+    pub fn bearer(&self) -> &BearerKind {
+        &self.0
+    }
+
+    pub fn address(&self) -> &str {
+        &self.1
+    }
+}
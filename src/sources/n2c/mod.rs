@@ -0,0 +1,4 @@
+mod run;
+pub mod setup;
+
+pub use setup::{Config, EventHandler, EventRetryPolicy, SourceBuilder};
@@ -0,0 +1,115 @@
+use pallas::network::{
+    miniprotocols::{
+        chainsync::{self, NextResponse},
+        Point,
+    },
+    multiplexer::Channel,
+};
+
+use crate::{mapper::EventWriter, model::EventData, Error};
+
+use super::setup::Cursor;
+
+/// Holds recently-seen points until they reach the confirmation depth
+///
+/// Points only leave the buffer — and are only reported as safely emitted — once
+/// at least `min_depth` newer points sit on top of them, which is what makes a
+/// rollback recoverable without having already pushed the rolled-back blocks.
+struct RollbackBuffer {
+    min_depth: usize,
+    points: Vec<Point>,
+}
+
+impl RollbackBuffer {
+    fn new(min_depth: usize) -> Self {
+        Self {
+            min_depth,
+            points: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, point: Point) {
+        self.points.push(point);
+    }
+
+    /// Drains points that have accrued enough confirmations, oldest first
+    fn pop_confirmed(&mut self) -> Vec<Point> {
+        let mut confirmed = Vec::new();
+
+        while self.points.len() > self.min_depth {
+            confirmed.push(self.points.remove(0));
+        }
+
+        confirmed
+    }
+
+    /// Discards any buffered points newer than the rollback target
+    ///
+    /// Returns whether the target was still buffered (ie: not yet emitted).
+    fn rollback_to(&mut self, point: &Point) -> bool {
+        if let Some(idx) = self.points.iter().position(|p| p == point) {
+            self.points.truncate(idx + 1);
+            true
+        } else {
+            self.points.clear();
+            false
+        }
+    }
+}
+
+/// Drives the chain-sync loop forever, emitting events down the pipeline
+///
+/// The channel is expected to be already intersected by `define_start_point`,
+/// so this function just pulls the next responses. As blocks leave the rollback
+/// buffer they are emitted and the shared [`Cursor`] is advanced to the last
+/// safely-emitted point, so the supervisor can re-intersect exactly there after
+/// a reconnect rather than replaying from the configured intersect.
+pub async fn observe_forever(
+    mut channel: Channel,
+    writer: EventWriter,
+    min_depth: usize,
+    cursor: Cursor,
+) -> Result<(), Error> {
+    let mut client = chainsync::N2CClient::new(&mut channel);
+
+    let mut buffer = RollbackBuffer::new(min_depth);
+
+    loop {
+        match client.request_next().await? {
+            NextResponse::RollForward(block, _tip) => {
+                let point = point_of(&block);
+                buffer.push(point);
+
+                for confirmed in buffer.pop_confirmed() {
+                    writer.append(EventData::Block)?;
+                    *cursor.lock().unwrap() = Some(confirmed);
+                }
+            }
+            NextResponse::RollBackward(point, _tip) => {
+                let still_buffered = buffer.rollback_to(&point);
+
+                writer.append(EventData::RollBack {
+                    block_slot: slot_of(&point),
+                })?;
+
+                // a target that is still buffered was never emitted, so the
+                // cursor keeps pointing at the last emitted point
+                if !still_buffered {
+                    *cursor.lock().unwrap() = Some(point);
+                }
+            }
+            NextResponse::Await => {}
+        }
+    }
+}
+
+fn point_of(block: &chainsync::BlockContent) -> Point {
+    block.point().unwrap_or(Point::Origin)
+}
+
+fn slot_of(point: &Point) -> u64 {
+    match point {
+        Point::Origin => 0,
+        Point::Specific(slot, _) => *slot,
+    }
+}
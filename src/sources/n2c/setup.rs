@@ -1,22 +1,32 @@
 use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
-use log::info;
+use log::{info, warn};
 
 use pallas::network::{
-    miniprotocols::{handshake, run_agent, MAINNET_MAGIC},
+    miniprotocols::{handshake, Point, MAINNET_MAGIC},
     multiplexer::Channel,
 };
 
+use crossbeam::channel::TryRecvError;
+
+use rand::Rng;
+
 use serde::Deserialize;
 
+use tokio::task::JoinHandle;
+
 use crate::{
     mapper::{Config as MapperConfig, EventWriter},
+    filters::selection::Predicate,
+    model::{Event, EventData},
     pipelining::{new_inter_stage_channel, PartialBootstrapResult, SourceProvider},
     sources::{
         common::{AddressArg, MagicArg, PointArg},
         define_start_point, setup_multiplexer, IntersectArg, RetryPolicy,
     },
-    utils::{ChainWellKnownInfo, WithUtils},
+    utils::{ChainWellKnownInfo, Utils, WithUtils},
     Error,
 };
 
@@ -51,58 +61,469 @@ pub struct Config {
     pub min_depth: usize,
 
     pub retry_policy: Option<RetryPolicy>,
+
+    /// Optional source-side event filter
+    ///
+    /// When set, only matching events are kept; everything else is discarded by
+    /// the [`EventWriter`] before being serialized and pushed onto the
+    /// inter-stage channel. Control events (rollbacks, reconnect markers)
+    /// always pass, whatever the predicate. Uses the same [`Predicate`] as the downstream
+    /// `selection` filter stage, so a source-side filter composes with (rather
+    /// than replaces) any filter configured later in the pipeline, while saving
+    /// the marshalling and channel cost of events nothing downstream wants.
+    pub filter: Option<Predicate>,
 }
 
-fn do_handshake(channel: &mut Channel, magic: u64) -> Result<(), Error> {
+/// Shared cursor tracking the last point safely emitted by the chain-sync loop
+///
+/// The loop updates this value as blocks leave the rollback buffer and are sent
+/// down the pipeline. The supervisor reads it to re-intersect exactly where the
+/// previous connection left off instead of replaying from the configured
+/// intersect on every reconnect.
+pub(crate) type Cursor = Arc<Mutex<Option<Point>>>;
+
+async fn do_handshake(channel: &mut Channel, magic: u64) -> Result<(), Error> {
     let versions = handshake::n2c::VersionTable::v1_and_above(magic);
-    let agent = run_agent(handshake::Initiator::initial(versions), channel)?;
-    info!("handshake output: {:?}", agent.output);
+    let mut client = handshake::Client::new(channel);
+    let output = client.handshake(versions).await?;
+    info!("handshake output: {:?}", output);
 
-    match agent.output {
-        handshake::Output::Accepted(_, _) => Ok(()),
+    match output {
+        handshake::Confirmation::Accepted(_, _) => Ok(()),
         _ => Err("couldn't agree on handshake version for client connection".into()),
     }
 }
 
+/// Turns a point drained from the rollback buffer into an intersect argument
+///
+/// Used by the supervisor to resume a reconnected chain-sync loop from the last
+/// safely-emitted point rather than from the configured intersect.
+fn point_as_intersect(point: &Point) -> IntersectArg {
+    match point {
+        Point::Origin => IntersectArg::Origin,
+        Point::Specific(slot, hash) => IntersectArg::Point(PointArg(*slot, hex::encode(hash))),
+    }
+}
+
+/// Renders a point as the `(slot, block-hash-hex)` carried by a reconnect event
+fn point_as_tuple(point: &Point) -> Option<(u64, String)> {
+    match point {
+        Point::Origin => None,
+        Point::Specific(slot, hash) => Some((*slot, hex::encode(hash))),
+    }
+}
+
+/// Backoff schedule derived from the source's [`RetryPolicy`]
+///
+/// Returns the delay to wait before the n-th (0-based) chain-sync reconnect:
+/// `base * factor^attempt`, capped at the configured ceiling. Optional jitter
+/// then adds up to half of that delay again (so a jittered delay can exceed the
+/// ceiling by up to 50%) to avoid thundering-herd reconnects when a node
+/// restarts.
+fn chainsync_backoff(policy: &RetryPolicy, attempt: u32) -> Duration {
+    let base = policy.chainsync_base_delay().as_millis() as f64;
+    let factor = policy.chainsync_backoff_factor.powi(attempt as i32);
+    let ceiling = policy.chainsync_max_delay().as_millis() as f64;
+
+    let mut delay = (base * factor).min(ceiling);
+
+    if policy.chainsync_jitter {
+        // spread reconnects across a random window so many clients dropped by
+        // the same node restart don't reconnect in lockstep
+        let spread = rand::thread_rng().gen_range(0.0..=0.5);
+        delay += delay * spread;
+    }
+
+    Duration::from_millis(delay as u64)
+}
+
 impl SourceProvider for WithUtils<Config> {
     fn bootstrap(&self) -> PartialBootstrapResult {
         let (output_tx, output_rx) = new_inter_stage_channel(None);
 
-        let mut muxer = setup_multiplexer(
-            &self.inner.address.0,
-            &self.inner.address.1,
-            &[0, 5],
-            &self.inner.retry_policy,
-        )?;
+        let writer = EventWriter::new(output_tx, self.utils.clone(), self.inner.mapper.clone())
+            .with_source_filter(self.inner.filter.clone());
 
-        let magic = match &self.inner.magic {
-            Some(m) => *m.deref(),
-            None => MAINNET_MAGIC,
+        let with_utils = WithUtils {
+            utils: self.utils.clone(),
+            inner: SupervisorConfig {
+                address: self.inner.address.clone(),
+                magic: match &self.inner.magic {
+                    Some(m) => *m.deref(),
+                    None => MAINNET_MAGIC,
+                },
+                #[allow(deprecated)]
+                since: self.inner.since.clone(),
+                intersect: self.inner.intersect.clone(),
+                min_depth: self.inner.min_depth,
+                retry_policy: self.inner.retry_policy.clone().unwrap_or_default(),
+            },
         };
 
-        let writer = EventWriter::new(output_tx, self.utils.clone(), self.inner.mapper.clone());
+        // spawn the chain-sync supervisor as a task on the pipeline-wide tokio
+        // runtime; the returned handle lets the pipeline abort it on shutdown
+        let handle = self
+            .utils
+            .runtime()
+            .spawn(async move { supervise_chain_sync(with_utils, writer).await });
+
+        Ok((handle, output_rx))
+    }
+}
+
+/// Inner config owned by the supervisor task once the source is bootstrapped
+struct SupervisorConfig {
+    address: AddressArg,
+    magic: u64,
+    since: Option<PointArg>,
+    intersect: Option<IntersectArg>,
+    min_depth: usize,
+    retry_policy: RetryPolicy,
+}
 
-        let mut hs_channel = muxer.use_channel(0);
-        do_handshake(&mut hs_channel, magic)?;
+/// Runs the chain-sync loop under a reconnecting supervisor
+///
+/// On any error the supervisor tears down the multiplexer and re-runs the full
+/// connection setup (`setup_multiplexer` + `do_handshake` +
+/// `define_start_point`), resuming from the last safely-emitted point held in
+/// the shared [`Cursor`]. Reconnects are spaced by [`chainsync_backoff`] and
+/// bounded by the policy's `chainsync_max_retries`; the retry counter is reset
+/// once a connection has stayed up for at least `chainsync_reset_after`. A
+/// synthetic [`EventData::Reconnecting`] event is emitted before each retry so
+/// downstream sinks can observe the gap.
+///
+/// Cancelling the task's `JoinHandle` drops the in-flight future at the next
+/// `.await`, tearing down the connection without the previous panic-on-thread.
+async fn supervise_chain_sync(with_utils: WithUtils<SupervisorConfig>, writer: EventWriter) {
+    let policy = with_utils.inner.retry_policy.clone();
+    let cursor: Cursor = Arc::new(Mutex::new(None));
 
-        let mut cs_channel = muxer.use_channel(5);
+    let mut attempt: u32 = 0;
 
-        let known_points = define_start_point(
-            &self.inner.intersect,
-            #[allow(deprecated)]
-            &self.inner.since,
-            &self.utils,
-            &mut cs_channel,
-        )?;
+    loop {
+        let started = Instant::now();
 
-        info!("starting chain sync from: {:?}", &known_points);
+        match run_chain_sync(&with_utils, &writer, &cursor).await {
+            Ok(()) => {
+                // the chain-sync loop is not expected to return on its own, but
+                // if it does we treat it as a clean connection close and retry
+                warn!("chain-sync loop returned without error, reconnecting");
+            }
+            Err(err) => {
+                warn!("chain-sync loop failed: {}", err);
+            }
+        }
+
+        if started.elapsed() >= policy.chainsync_reset_after() {
+            attempt = 0;
+        }
+
+        if attempt >= policy.chainsync_max_retries {
+            warn!(
+                "chain-sync loop exhausted {} reconnect attempts, giving up",
+                policy.chainsync_max_retries
+            );
+            return;
+        }
+
+        let backoff = chainsync_backoff(&policy, attempt);
+        attempt += 1;
+
+        let from = cursor.lock().unwrap().clone();
+        if let Err(err) = writer.append(EventData::Reconnecting {
+            attempt,
+            last_point: from.as_ref().and_then(point_as_tuple),
+        }) {
+            warn!("unable to emit reconnecting event: {}", err);
+        }
+
+        info!(
+            "reconnecting chain-sync (attempt {}) in {:?}",
+            attempt, backoff
+        );
+        tokio::time::sleep(backoff).await;
+    }
+}
+
+/// Performs one full connection attempt and drives the chain-sync loop to error
+async fn run_chain_sync(
+    with_utils: &WithUtils<SupervisorConfig>,
+    writer: &EventWriter,
+    cursor: &Cursor,
+) -> Result<(), Error> {
+    let cfg = &with_utils.inner;
+
+    let mut muxer = setup_multiplexer(
+        &cfg.address.0,
+        &cfg.address.1,
+        &[0, 5],
+        &Some(cfg.retry_policy.clone()),
+    )
+    .await?;
+
+    let mut hs_channel = muxer.use_channel(0);
+    do_handshake(&mut hs_channel, cfg.magic).await?;
+
+    let mut cs_channel = muxer.use_channel(5);
+
+    // resume from the last safely-emitted point when we have one, otherwise
+    // fall back to the configured intersect for the first connection
+    let resume = cursor.lock().unwrap().as_ref().map(point_as_intersect);
+    let intersect = match &resume {
+        Some(_) => &resume,
+        None => &cfg.intersect,
+    };
+
+    let known_points = define_start_point(
+        intersect,
+        #[allow(deprecated)]
+        &cfg.since,
+        &with_utils.utils,
+        &mut cs_channel,
+    )
+    .await?;
+
+    info!("starting chain sync from: {:?}", &known_points);
+
+    observe_forever(cs_channel, writer.clone(), cfg.min_depth, cursor.clone()).await
+}
+
+/// Callback invoked for every event produced by an embedded n2c source
+///
+/// Implement this trait to consume chain events directly from a host
+/// application instead of wiring a full pipeline into a built-in sink. The
+/// handler is called once per event, in order; returning `Err` triggers the
+/// builder's per-event retry policy (see [`SourceBuilder::event_retry_policy`]).
+pub trait EventHandler {
+    fn handle(&self, event: Event) -> Result<(), Error>;
+}
+
+impl<F> EventHandler for F
+where
+    F: Fn(Event) -> Result<(), Error>,
+{
+    fn handle(&self, event: Event) -> Result<(), Error> {
+        (self)(event)
+    }
+}
+
+/// Bounded retry policy applied to each [`EventHandler`] invocation
+///
+/// Unlike the connection-level [`RetryPolicy`], this governs how many times a
+/// single event is re-delivered to the handler before it is handed to the
+/// dead-letter callback. Defaults to a handful of retries with exponential
+/// backoff.
+#[derive(Debug, Clone)]
+pub struct EventRetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub backoff_factor: f64,
+    pub max_delay: Duration,
+}
 
-        let min_depth = self.inner.min_depth;
-        let handle = std::thread::spawn(move || {
-            observe_forever(cs_channel, writer, known_points, min_depth)
-                .expect("chainsync loop failed");
+impl Default for EventRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(100),
+            backoff_factor: 2.0,
+            max_delay: Duration::from_secs(10),
+        }
+    }
+}
+
+impl EventRetryPolicy {
+    fn backoff(&self, attempt: u32) -> Duration {
+        let base = self.base_delay.as_millis() as f64;
+        let delay = (base * self.backoff_factor.powi(attempt as i32))
+            .min(self.max_delay.as_millis() as f64);
+        Duration::from_millis(delay as u64)
+    }
+}
+
+/// Callback invoked for an event that exhausted its per-event retries
+type DeadLetter = Arc<dyn Fn(Event, Error) + Send + Sync>;
+
+/// Builder for embedding the n2c source in a host application
+///
+/// Reuses the same [`EventWriter`]/`observe_forever` machinery as the pipeline
+/// source, but delivers events to a user-supplied [`EventHandler`] rather than
+/// onto an inter-stage channel feeding a built-in sink.
+pub struct SourceBuilder<H: EventHandler + Send + Sync + 'static> {
+    utils: Arc<Utils>,
+    address: AddressArg,
+    magic: Option<MagicArg>,
+    intersect: Option<IntersectArg>,
+    min_depth: usize,
+    mapper: MapperConfig,
+    retry_policy: Option<RetryPolicy>,
+    event_retry: EventRetryPolicy,
+    filter: Option<Predicate>,
+    handler: H,
+    dead_letter: Option<DeadLetter>,
+}
+
+impl<H: EventHandler + Send + Sync + 'static> SourceBuilder<H> {
+    pub fn new(utils: Arc<Utils>, address: AddressArg, handler: H) -> Self {
+        Self {
+            utils,
+            address,
+            magic: None,
+            intersect: None,
+            min_depth: 0,
+            mapper: MapperConfig::default(),
+            retry_policy: None,
+            event_retry: EventRetryPolicy::default(),
+            filter: None,
+            handler,
+            dead_letter: None,
+        }
+    }
+
+    pub fn magic(mut self, magic: MagicArg) -> Self {
+        self.magic = Some(magic);
+        self
+    }
+
+    pub fn intersect(mut self, intersect: IntersectArg) -> Self {
+        self.intersect = Some(intersect);
+        self
+    }
+
+    pub fn min_depth(mut self, min_depth: usize) -> Self {
+        self.min_depth = min_depth;
+        self
+    }
+
+    pub fn mapper(mut self, mapper: MapperConfig) -> Self {
+        self.mapper = mapper;
+        self
+    }
+
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Per-event retry policy for handler invocations
+    pub fn event_retry_policy(mut self, policy: EventRetryPolicy) -> Self {
+        self.event_retry = policy;
+        self
+    }
+
+    /// Source-side event filter, discarding non-matching events before the
+    /// handler is ever invoked; control events (rollbacks, reconnect markers)
+    /// always reach the handler
+    pub fn filter(mut self, filter: Predicate) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Callback invoked for events that exhaust their per-event retries
+    pub fn dead_letter<F>(mut self, dead_letter: F) -> Self
+    where
+        F: Fn(Event, Error) + Send + Sync + 'static,
+    {
+        self.dead_letter = Some(Arc::new(dead_letter));
+        self
+    }
+
+    /// Bootstraps the embedded source
+    ///
+    /// Spawns the supervised chain-sync loop onto the shared tokio runtime and
+    /// a consumer task that drains events and delivers each one to the handler.
+    /// Returns the consumer task handle; aborting it stops delivery and tears
+    /// down the supervisor (it is aborted when the drain task ends).
+    pub fn bootstrap(self) -> Result<JoinHandle<()>, Error> {
+        let (output_tx, output_rx) = new_inter_stage_channel(None);
+
+        let writer = EventWriter::new(output_tx, self.utils.clone(), self.mapper.clone())
+            .with_source_filter(self.filter.clone());
+
+        let with_utils = WithUtils {
+            utils: self.utils.clone(),
+            inner: SupervisorConfig {
+                address: self.address.clone(),
+                magic: match &self.magic {
+                    Some(m) => *m.deref(),
+                    None => MAINNET_MAGIC,
+                },
+                since: None,
+                intersect: self.intersect.clone(),
+                min_depth: self.min_depth,
+                retry_policy: self.retry_policy.clone().unwrap_or_default(),
+            },
+        };
+
+        let runtime = self.utils.runtime();
+
+        let supervisor = runtime.spawn(async move { supervise_chain_sync(with_utils, writer).await });
+
+        let handler = self.handler;
+        let event_retry = self.event_retry;
+        let dead_letter = self.dead_letter;
+
+        // drain asynchronously so aborting the returned handle actually stops
+        // delivery at the next yield; the guard aborts the supervisor (and frees
+        // its output_tx) when this task ends, whether by abort or disconnect
+        let handle = runtime.spawn(async move {
+            let _supervisor = AbortOnDrop(supervisor);
+
+            loop {
+                match output_rx.try_recv() {
+                    Ok(event) => {
+                        deliver_event(&handler, &event_retry, &dead_letter, event).await
+                    }
+                    Err(TryRecvError::Empty) => {
+                        tokio::time::sleep(Duration::from_millis(50)).await
+                    }
+                    Err(TryRecvError::Disconnected) => break,
+                }
+            }
         });
 
-        Ok((handle, output_rx))
+        Ok(handle)
+    }
+}
+
+/// Aborts the wrapped task when dropped, so cancelling the drain task tears the
+/// supervisor down with it
+struct AbortOnDrop(JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Delivers a single event to the handler, honoring the per-event retry policy
+async fn deliver_event<H: EventHandler>(
+    handler: &H,
+    retry: &EventRetryPolicy,
+    dead_letter: &Option<DeadLetter>,
+    event: Event,
+) {
+    let mut attempt = 0;
+
+    loop {
+        match handler.handle(event.clone()) {
+            Ok(()) => return,
+            Err(err) if attempt >= retry.max_retries => {
+                warn!("event handler exhausted retries: {}", err);
+                if let Some(dead_letter) = dead_letter {
+                    dead_letter(event, err);
+                }
+                return;
+            }
+            Err(err) => {
+                let backoff = retry.backoff(attempt);
+                warn!(
+                    "event handler failed (attempt {}), retrying in {:?}: {}",
+                    attempt, backoff, err
+                );
+                tokio::time::sleep(backoff).await;
+                attempt += 1;
+            }
+        }
     }
 }
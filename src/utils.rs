@@ -0,0 +1,47 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+/// Well-known chain parameters shared pipeline-wide
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ChainWellKnownInfo {
+    pub magic: u64,
+    pub byron_epoch_length: u64,
+    pub byron_slot_length: u64,
+}
+
+/// Shared, pipeline-wide utilities handed to every stage
+pub struct Utils {
+    pub well_known: ChainWellKnownInfo,
+    runtime: tokio::runtime::Runtime,
+}
+
+impl Utils {
+    pub fn new(well_known: ChainWellKnownInfo) -> Result<Self, std::io::Error> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()?;
+
+        Ok(Self {
+            well_known,
+            runtime,
+        })
+    }
+
+    /// The shared tokio runtime sources and sinks multiplex their tasks onto
+    pub fn runtime(&self) -> &tokio::runtime::Runtime {
+        &self.runtime
+    }
+}
+
+/// Wraps a stage config together with the pipeline-wide [`Utils`]
+pub struct WithUtils<T> {
+    pub utils: Arc<Utils>,
+    pub inner: T,
+}
+
+impl<T> WithUtils<T> {
+    pub fn new(inner: T, utils: Arc<Utils>) -> Self {
+        Self { utils, inner }
+    }
+}